@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+const CONFIG_DIR_NAME: &str = "loop";
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+#[derive(Deserialize, Default)]
+struct RawConfig {
+    tab_stop: Option<usize>,
+    quit_times: Option<u8>,
+    status_bg_color: Option<[u8; 3]>,
+    status_fg_color: Option<[u8; 3]>,
+    keybindings: Option<HashMap<String, String>>,
+}
+
+pub struct Config {
+    pub tab_stop: usize,
+    pub quit_times: u8,
+    pub status_bg_color: (u8, u8, u8),
+    pub status_fg_color: (u8, u8, u8),
+    pub keybindings: HashMap<String, String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            tab_stop: 4,
+            quit_times: 2,
+            status_bg_color: (0, 50, 100),
+            status_fg_color: (255, 255, 255),
+            keybindings: HashMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Load `config.toml` from the user's config directory (e.g.
+    /// `~/.config/loop/config.toml`), falling back to defaults for any
+    /// field that's missing, and to all-defaults if the file is absent or
+    /// can't be parsed.
+    pub fn load() -> Self {
+        let raw = Self::config_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| toml::from_str::<RawConfig>(&contents).ok())
+            .unwrap_or_default();
+
+        let defaults = Self::default();
+        Self {
+            tab_stop: raw.tab_stop.unwrap_or(defaults.tab_stop),
+            quit_times: raw.quit_times.unwrap_or(defaults.quit_times),
+            status_bg_color: raw
+                .status_bg_color
+                .map_or(defaults.status_bg_color, rgb_tuple),
+            status_fg_color: raw
+                .status_fg_color
+                .map_or(defaults.status_fg_color, rgb_tuple),
+            keybindings: raw.keybindings.unwrap_or(defaults.keybindings),
+        }
+    }
+
+    fn config_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join(CONFIG_DIR_NAME).join(CONFIG_FILE_NAME))
+    }
+}
+
+fn rgb_tuple(rgb: [u8; 3]) -> (u8, u8, u8) {
+    (rgb[0], rgb[1], rgb[2])
+}
@@ -0,0 +1,195 @@
+use crate::FileType;
+use crate::Position;
+use crate::Row;
+
+use std::fs;
+use std::io::{Error, Write};
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Which way an incremental search scans from its anchor position.
+#[derive(Clone, Copy, PartialEq)]
+pub enum SearchDirection {
+    Forward,
+    Backward,
+}
+
+#[derive(Default)]
+pub struct Document {
+    rows: Vec<Row>,
+    pub file_name: Option<String>,
+    dirty: bool,
+    file_type: FileType,
+}
+
+impl Document {
+    pub fn open(filename: &str) -> Result<Self, std::io::Error> {
+        let contents = fs::read_to_string(filename)?;
+        let file_type = FileType::from(filename);
+        let mut rows = Vec::new();
+        for value in contents.lines() {
+            let mut row = Row::from(value);
+            row.highlight(file_type.highlighting_options(), None);
+            rows.push(row);
+        }
+        Ok(Self {
+            rows,
+            file_name: Some(filename.to_string()),
+            dirty: false,
+            file_type,
+        })
+    }
+
+    pub fn file_type(&self) -> String {
+        self.file_type.name()
+    }
+
+    pub fn row(&self, index: usize) -> Option<&Row> {
+        self.rows.get(index)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn insert(&mut self, at: &Position, c: char) {
+        if c == '\n' {
+            self.insert_newline(at);
+            return;
+        }
+        if at.y == self.rows.len() {
+            let mut row = Row::default();
+            row.insert(0, c);
+            self.rows.push(row);
+        } else if at.y < self.rows.len() {
+            let row = &mut self.rows[at.y];
+            row.insert(at.x, c);
+        }
+        self.dirty = true;
+        self.unhighlight_rows(at.y);
+    }
+
+    fn insert_newline(&mut self, at: &Position) {
+        if at.y > self.rows.len() {
+            return;
+        }
+        if at.y == self.rows.len() {
+            self.rows.push(Row::default());
+            return;
+        }
+        let new_row = self.rows[at.y].split(at.x);
+        self.rows.insert(at.y + 1, new_row);
+    }
+
+    pub fn delete(&mut self, at: &Position) {
+        let len = self.rows.len();
+        if at.y >= len {
+            return;
+        }
+        self.dirty = true;
+        if at.x == self.rows[at.y].len() && at.y + 1 < len {
+            let next_row = self.rows.remove(at.y + 1);
+            let row = &mut self.rows[at.y];
+            row.append(&next_row);
+        } else {
+            let row = &mut self.rows[at.y];
+            row.delete(at.x);
+        }
+        self.unhighlight_rows(at.y);
+    }
+
+    pub fn set_row_text(&mut self, y: usize, text: &str) {
+        if y >= self.rows.len() {
+            return;
+        }
+        self.rows[y] = Row::from(text);
+        self.dirty = true;
+        self.unhighlight_rows(y);
+    }
+
+    fn unhighlight_rows(&mut self, start: usize) {
+        let start = start.saturating_sub(1);
+        for row in self.rows.iter_mut().skip(start) {
+            row.is_highlighted = false;
+        }
+    }
+
+    pub fn save(&mut self) -> Result<(), Error> {
+        if let Some(file_name) = self.file_name.clone() {
+            let mut file = fs::File::create(&file_name)?;
+            self.file_type = FileType::from(&file_name);
+            for row in &mut self.rows {
+                file.write_all(row.as_bytes())?;
+                file.write_all(b"\n")?;
+                row.highlight(self.file_type.highlighting_options(), None);
+            }
+            self.dirty = false;
+        }
+        Ok(())
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    pub fn highlight(&mut self, word: Option<&str>) {
+        let opts = self.file_type.highlighting_options().clone();
+        for row in &mut self.rows {
+            row.highlight(&opts, word);
+        }
+    }
+
+    // search row-by-row starting at `after`, wrapping around the document
+    // boundary, and stop as soon as one full lap has been made so a query
+    // with no match doesn't loop forever
+    pub fn find(
+        &mut self,
+        query: &str,
+        after: &Position,
+        direction: SearchDirection,
+    ) -> Option<Position> {
+        if query.is_empty() || self.rows.is_empty() {
+            return None;
+        }
+
+        let query_len = query.graphemes(true).count();
+        let num_rows = self.rows.len();
+        let mut y = after.y.min(num_rows - 1);
+        let mut x = after.x;
+
+        for _ in 0..=num_rows {
+            let row = &mut self.rows[y];
+            let found = match direction {
+                SearchDirection::Forward => row.find(query, x),
+                SearchDirection::Backward => row.rfind(query, x),
+            };
+            if let Some(x) = found {
+                row.highlight_match(x, x.saturating_add(query_len));
+                return Some(Position { x, y });
+            }
+
+            match direction {
+                SearchDirection::Forward => {
+                    y = if y.saturating_add(1) == num_rows { 0 } else { y + 1 };
+                    x = 0;
+                }
+                SearchDirection::Backward => {
+                    y = if y == 0 { num_rows - 1 } else { y - 1 };
+                    x = self.rows[y].len();
+                }
+            }
+        }
+        None
+    }
+
+    // rows keep stale `Type::Match` highlighting until they're recomputed;
+    // call this once an incremental search ends so the next full
+    // `highlight` pass (with no search word) actually clears it
+    pub fn clear_search_highlight(&mut self) {
+        self.unhighlight_rows(0);
+    }
+}
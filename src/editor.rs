@@ -1,17 +1,18 @@
-use crate::Terminal;
+use crate::config::Config;
+use crate::document::SearchDirection;
+use crate::scripting::{EditorApi, Scripting};
+use crate::terminal::Key;
 use crate::Document;
 use crate::Row;
+use crate::Terminal;
 
 use std::env;
 use std::time::Duration;
 use std::time::Instant;
 
-use termion::{event::Key, color}; 
+use crossterm::style::Color;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
-const STATUS_BG_COLOR: color::Rgb = color::Rgb(0, 50, 100);
-const STATUS_FG_COLOR: color::Rgb = color::Rgb(255, 255, 255);
-const QUIT_TIMES: u8 = 2;
 
 #[derive(Default)]
 pub struct Position {
@@ -32,6 +33,12 @@ pub struct Editor {
     // message to display in the status bar
     status_message: StatusMessage,
     quit_times: u8,
+    highlighted_word: Option<String>,
+    // rendered cursor column (post tab-expansion), kept in sync with
+    // cursor_position.x by scroll()
+    render_x: usize,
+    config: Config,
+    scripting: Scripting,
 }
 
 struct StatusMessage {
@@ -50,8 +57,11 @@ impl StatusMessage {
 
 impl Editor {
     pub fn default() -> Self {
+        let config = Config::load();
         let args: Vec<String> = env::args().collect();
-        let mut initial_status = String::from("HELP: Ctrl-F = find | Ctrl-S = save | Ctrl-Q = quit");
+        let mut initial_status = String::from(
+            "HELP: Ctrl-F = find | Ctrl-S = save | Ctrl-E = run script | Ctrl-Q = quit",
+        );
         let document = if args.len() > 1 {
             let file_name = &args[1];
             let doc = Document::open(&file_name);
@@ -72,7 +82,11 @@ impl Editor {
             document,
             offset: Position::default(),
             status_message: StatusMessage::from(initial_status),
-            quit_times: QUIT_TIMES,
+            quit_times: config.quit_times,
+            highlighted_word: None,
+            render_x: 0,
+            scripting: Scripting::load(),
+            config,
         }
     }
 
@@ -83,7 +97,7 @@ impl Editor {
         let start = self.offset.x;
         let end = self.offset.x + width;
 
-        let row = row.render(start, end);
+        let row = row.render(start, end, self.config.tab_stop);
         println!("{}\r", row);
     }
 
@@ -101,19 +115,20 @@ impl Editor {
         }
     }
 
-    fn refresh_screen(&self) -> Result<(), std::io::Error>{
+    fn refresh_screen(&mut self) -> Result<(), std::io::Error> {
         Terminal::cursor_hide();
         Terminal::cursor_position(&Position::default());
         if self.should_quit {
             Terminal::clear_screen();
             println!("Goodbye!\r");
         } else {
+            self.document.highlight(self.highlighted_word.as_deref());
             self.draw_rows();
             self.draw_status_bar();
             self.draw_message_bar();
             // Position the cursor properly when scrolling up
             Terminal::cursor_position(&Position {
-                x: self.cursor_position.x.saturating_sub(self.offset.x),
+                x: self.render_x.saturating_sub(self.offset.x),
                 y: self.cursor_position.y.saturating_sub(self.offset.y),
             });
         }
@@ -130,7 +145,7 @@ impl Editor {
             }
             self.document.file_name = new_name;
         }
-            
+
         if self.document.save().is_ok() {
             self.status_message = StatusMessage::from("File saved succesfully".to_string());
         } else {
@@ -138,8 +153,58 @@ impl Editor {
         }
     }
 
+    // incremental search: Right/Down jump to the next match, Left/Up to
+    // the previous one, any other key restarts the scan from wherever
+    // typing last left the cursor. Esc restores the pre-search cursor
+    // position; Enter keeps wherever the search landed.
+    fn search(&mut self) {
+        let old_position = Position {
+            x: self.cursor_position.x,
+            y: self.cursor_position.y,
+        };
+        let mut direction = SearchDirection::Forward;
+
+        let query = self
+            .prompt(
+                "Search (ESC to cancel, Arrows to navigate): ",
+                |editor, key, query| {
+                    let mut moved = false;
+                    match key {
+                        Key::Right | Key::Down => {
+                            direction = SearchDirection::Forward;
+                            editor.move_cursor(Key::Right);
+                            moved = true;
+                        }
+                        Key::Left | Key::Up => direction = SearchDirection::Backward,
+                        _ => direction = SearchDirection::Forward,
+                    }
+
+                    if let Some(position) =
+                        editor
+                            .document
+                            .find(query, &editor.cursor_position, direction)
+                    {
+                        editor.cursor_position = position;
+                        editor.scroll();
+                    } else if moved {
+                        editor.move_cursor(Key::Left);
+                    }
+                    editor.highlighted_word = Some(query.to_string());
+                },
+            )
+            .unwrap_or(None);
+
+        if query.is_none() {
+            self.cursor_position = old_position;
+            self.scroll();
+        }
+        self.highlighted_word = None;
+        self.document.clear_search_highlight();
+    }
+
     fn process_kepress(&mut self) -> Result<(), std::io::Error> {
         let pressed_key = Terminal::read_key()?;
+        let run_script_key = self.run_script_keybinding();
         match pressed_key {
             Key::Ctrl('q') => {
                 if self.quit_times > 0 && self.document.is_dirty() {
@@ -151,33 +216,25 @@ impl Editor {
                     return Ok(());
                 }
                 self.should_quit = true;
-            },
-            Key::Ctrl('f') => {
-                if let Some(query) = self
-                .prompt("Search: ", |editor, _, query| {
-                    if let Some(position) = editor.document.find(&query) {
-                        editor.cursor_position = position;
-                        editor.scroll();
-                    }
-                })
-                .unwrap_or(None)
-                {
-                    if let Some(position) = self.document.find(&query[..]) {
-                        self.cursor_position = position;
-                    } else {
-                        self.status_message = StatusMessage::from(format!("Not found :{}", query));
-                    }       
-                }
-            },
+            }
+            Key::Ctrl('f') => self.search(),
             Key::Ctrl('s') => self.save(),
+            Key::Ctrl(c) if c == run_script_key => {
+                if let Some(command) = self.prompt("Run command: ", |_, _, _| {}).unwrap_or(None) {
+                    self.run_script(&command);
+                }
+            }
             Key::Ctrl('h') => {
-                self.status_message = StatusMessage::from("HELP: Ctrl-F = find | Ctrl-S = save | Ctrl-Q = quit".to_string());
+                self.status_message = StatusMessage::from(
+                    "HELP: Ctrl-F = find | Ctrl-S = save | Ctrl-E = run script | Ctrl-Q = quit"
+                        .to_string(),
+                );
             }
             Key::Char(c) => {
                 // don't move cursor to the right if enter is pressed
                 self.move_cursor(Key::Right);
                 self.document.insert(&self.cursor_position, c);
-            },
+            }
             Key::Delete => self.document.delete(&self.cursor_position),
             Key::Backspace => {
                 // Backspace = going left and perform delete
@@ -197,8 +254,8 @@ impl Editor {
             _ => (),
         }
         self.scroll();
-        if self.quit_times < QUIT_TIMES {
-            self.quit_times = QUIT_TIMES;
+        if self.quit_times < self.config.quit_times {
+            self.quit_times = self.config.quit_times;
             self.status_message = StatusMessage::from(String::new());
         }
         Ok(())
@@ -208,6 +265,13 @@ impl Editor {
         let Position { x, y } = self.cursor_position;
         let width = self.terminal.size().width as usize;
         let height = self.terminal.size().height as usize;
+
+        self.render_x = self
+            .document
+            .row(y)
+            .map_or(0, |row| row.cx_to_rx(x, self.config.tab_stop));
+        let render_x = self.render_x;
+
         let mut offset = &mut self.offset;
 
         // vertical scrolling
@@ -218,10 +282,10 @@ impl Editor {
             offset.y = offset.y.saturating_add(1);
         }
 
-        // horizontal scrolling
-        if x < offset.x {
-            offset.x = x;   
-        } else if x >= offset.x.saturating_add(width) {
+        // horizontal scrolling, driven by the rendered column so tabs scroll correctly
+        if render_x < offset.x {
+            offset.x = render_x;
+        } else if render_x >= offset.x.saturating_add(width) {
             // scroll horizontally one letter at a time
             offset.x = offset.x.saturating_add(1);
         }
@@ -266,9 +330,10 @@ impl Editor {
             self.document.len(),
             modified_indicator,
         );
-        
-        let line_indicator = format! (
-            "Ln {}, Col {}",
+
+        let line_indicator = format!(
+            "{} | Ln {}, Col {}",
+            self.document.file_type(),
             self.cursor_position.y.saturating_add(1),
             self.cursor_position.x.saturating_add(1),
         );
@@ -279,8 +344,8 @@ impl Editor {
         }
         status = format!("{}{}", status, line_indicator);
         status.truncate(width);
-        Terminal::set_bg_color(STATUS_BG_COLOR);
-        Terminal::set_fg_color(STATUS_FG_COLOR);
+        Terminal::set_bg_color(Self::rgb(self.config.status_bg_color));
+        Terminal::set_fg_color(Self::rgb(self.config.status_fg_color));
         println!("{}\r", status);
 
         // reset the bg and fg colors so that only status is printed in these colors
@@ -297,7 +362,7 @@ impl Editor {
             print!("{}", text);
         }
     }
-    
+
     fn draw_welcome_message(&self) {
         let mut welcome_message = format!("Editr -- version {}", VERSION);
         let width = self.terminal.size().width as usize;
@@ -309,7 +374,7 @@ impl Editor {
         println!("{}\r", welcome_message);
     }
 
-    fn move_cursor(&mut self, key: Key)  {
+    fn move_cursor(&mut self, key: Key) {
         let terminal_height = self.terminal.size().height as usize;
         let Position { mut x, mut y } = self.cursor_position;
         // let size = self.terminal.size();
@@ -326,7 +391,7 @@ impl Editor {
                 if y < height {
                     y = y.saturating_add(1);
                 }
-            },
+            }
             Key::Left => {
                 if x > 0 {
                     x = x.saturating_sub(1);
@@ -335,7 +400,7 @@ impl Editor {
                     // set x to width of above row
                     x = self.document.row(y).unwrap().len();
                 }
-            },
+            }
             Key::Right => {
                 if x < width {
                     x += 1;
@@ -343,21 +408,21 @@ impl Editor {
                     y += 1;
                     x = 0;
                 }
-            },
+            }
             Key::PageUp => {
                 y = if y > terminal_height {
                     y - terminal_height
                 } else {
                     0
                 };
-            },
+            }
             Key::PageDown => {
                 y = if y.saturating_add(terminal_height) < height {
                     y + terminal_height as usize
                 } else {
                     height
                 };
-            },
+            }
             Key::Home => x = 0,
             Key::End => x = width,
             _ => (),
@@ -370,13 +435,13 @@ impl Editor {
         if x > width {
             x = width;
         }
-        
+
         self.cursor_position = Position { x, y };
     }
 
-    fn prompt<C>(&mut self, prompt: &str, callback: C) -> Result<Option<String>, std::io::Error>
-        where
-            C: Fn(&mut Self, Key, &String)
+    fn prompt<C>(&mut self, prompt: &str, mut callback: C) -> Result<Option<String>, std::io::Error>
+    where
+        C: FnMut(&mut Self, Key, &String),
     {
         let mut result = String::new();
         loop {
@@ -389,17 +454,17 @@ impl Editor {
                     if !result.is_empty() {
                         result.truncate(result.len() - 1);
                     }
-                },
+                }
                 Key::Char('\n') => break,
                 Key::Char(c) => {
                     if !c.is_control() {
                         result.push(c);
                     }
-                },
+                }
                 Key::Esc => {
                     result.truncate(0);
                     break;
-                },
+                }
                 _ => (),
             }
             callback(self, key, &result);
@@ -410,6 +475,59 @@ impl Editor {
         }
         Ok(Some(result))
     }
+
+    // run the Rhai function `name` from the user's scripts.rhai against the
+    // current line/cursor, applying whatever it changes back onto the
+    // document; errors surface in the message bar instead of panicking
+    fn run_script(&mut self, name: &str) {
+        let current_line = self
+            .document
+            .row(self.cursor_position.y)
+            .map_or_else(String::new, |row| row.as_str().to_string());
+        let api = EditorApi::new(
+            current_line,
+            Position {
+                x: self.cursor_position.x,
+                y: self.cursor_position.y,
+            },
+        );
+
+        match self.scripting.run(name, api) {
+            Ok(state) => {
+                self.document
+                    .set_row_text(self.cursor_position.y, &state.current_line);
+                self.cursor_position = state.cursor;
+                for c in state.insert_queue.chars() {
+                    self.document.insert(&self.cursor_position, c);
+                    self.move_cursor(Key::Right);
+                }
+                if let Some(message) = state.status_message {
+                    self.status_message = StatusMessage::from(message);
+                }
+            }
+            Err(error) => {
+                self.status_message = StatusMessage::from(format!("Script error: {}", error));
+            }
+        }
+    }
+
+    fn rgb(color: (u8, u8, u8)) -> Color {
+        Color::Rgb {
+            r: color.0,
+            g: color.1,
+            b: color.2,
+        }
+    }
+
+    // the `run_script` binding defaults to Ctrl-E, overridable via the
+    // `keybindings` table in config.toml (e.g. `run_script = "r"`)
+    fn run_script_keybinding(&self) -> char {
+        self.config
+            .keybindings
+            .get("run_script")
+            .and_then(|key| key.chars().next())
+            .unwrap_or('e')
+    }
 }
 
 fn die(e: std::io::Error) {
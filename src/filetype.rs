@@ -0,0 +1,156 @@
+#[derive(Clone)]
+pub struct FileType {
+    name: String,
+    hl_opts: HighlightingOptions,
+}
+
+#[derive(Clone, Default)]
+pub struct HighlightingOptions {
+    numbers: bool,
+    strings: bool,
+    characters: bool,
+    comments: bool,
+    comment_start: String,
+    primary_keywords: Vec<String>,
+    secondary_keywords: Vec<String>,
+}
+
+impl Default for FileType {
+    fn default() -> Self {
+        Self {
+            name: String::from("No filetype"),
+            hl_opts: HighlightingOptions::default(),
+        }
+    }
+}
+
+impl FileType {
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn highlighting_options(&self) -> &HighlightingOptions {
+        &self.hl_opts
+    }
+
+    pub fn from(file_name: &str) -> Self {
+        if file_name.ends_with(".rs") {
+            return Self {
+                name: String::from("Rust"),
+                hl_opts: HighlightingOptions {
+                    numbers: true,
+                    strings: true,
+                    characters: true,
+                    comments: true,
+                    comment_start: String::from("//"),
+                    primary_keywords: vec![
+                        "as", "break", "const", "continue", "crate", "else", "enum", "extern",
+                        "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod",
+                        "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct",
+                        "super", "trait", "true", "type", "unsafe", "use", "where", "while", "dyn",
+                        "async", "await",
+                    ]
+                    .iter()
+                    .map(|s| (*s).to_string())
+                    .collect(),
+                    secondary_keywords: vec![
+                        "bool", "char", "i8", "i16", "i32", "i64", "isize", "u8", "u16", "u32",
+                        "u64", "usize", "f32", "f64", "String", "Vec", "Option", "Result", "Box",
+                    ]
+                    .iter()
+                    .map(|s| (*s).to_string())
+                    .collect(),
+                },
+            };
+        }
+
+        if file_name.ends_with(".c") || file_name.ends_with(".h") || file_name.ends_with(".cpp") {
+            return Self {
+                name: String::from("C"),
+                hl_opts: HighlightingOptions {
+                    numbers: true,
+                    strings: true,
+                    characters: true,
+                    comments: true,
+                    comment_start: String::from("//"),
+                    primary_keywords: vec![
+                        "auto", "break", "case", "const", "continue", "default", "do", "else",
+                        "enum", "extern", "for", "goto", "if", "return", "sizeof", "static",
+                        "struct", "switch", "typedef", "union", "volatile", "while",
+                    ]
+                    .iter()
+                    .map(|s| (*s).to_string())
+                    .collect(),
+                    secondary_keywords: vec![
+                        "char", "double", "float", "int", "long", "short", "signed", "unsigned",
+                        "void",
+                    ]
+                    .iter()
+                    .map(|s| (*s).to_string())
+                    .collect(),
+                },
+            };
+        }
+
+        if file_name.ends_with(".py") {
+            return Self {
+                name: String::from("Python"),
+                hl_opts: HighlightingOptions {
+                    numbers: true,
+                    strings: true,
+                    characters: false,
+                    comments: true,
+                    comment_start: String::from("#"),
+                    primary_keywords: vec![
+                        "and", "as", "assert", "break", "class", "continue", "def", "del", "elif",
+                        "else", "except", "finally", "for", "from", "global", "if", "import", "in",
+                        "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try",
+                        "while", "with", "yield",
+                    ]
+                    .iter()
+                    .map(|s| (*s).to_string())
+                    .collect(),
+                    secondary_keywords: vec![
+                        "int", "float", "str", "bool", "list", "dict", "set", "tuple", "None",
+                        "True", "False",
+                    ]
+                    .iter()
+                    .map(|s| (*s).to_string())
+                    .collect(),
+                },
+            };
+        }
+
+        Self::default()
+    }
+}
+
+impl HighlightingOptions {
+    pub fn numbers(&self) -> bool {
+        self.numbers
+    }
+
+    pub fn strings(&self) -> bool {
+        self.strings
+    }
+
+    pub fn characters(&self) -> bool {
+        self.characters
+    }
+
+    pub fn comments(&self) -> bool {
+        self.comments
+    }
+
+    pub fn comment_start(&self) -> &str {
+        &self.comment_start
+    }
+
+    pub fn primary_keywords(&self) -> &Vec<String> {
+        &self.primary_keywords
+    }
+
+    pub fn secondary_keywords(&self) -> &Vec<String> {
+        &self.secondary_keywords
+    }
+}
@@ -6,12 +6,16 @@ mod terminal;
 mod row;
 mod document;
 mod highlighting;
+mod filetype;
+mod config;
+mod scripting;
 
 use editor::Editor;
 pub use editor::Position;
 pub use terminal::Terminal;
 pub use document::Document;
 pub use row::Row;
+pub use filetype::FileType;
 
 fn main() {
     let args = std::env::args();
@@ -1,14 +1,16 @@
+use crate::filetype::HighlightingOptions;
 use crate::highlighting;
 
 use std::cmp;
 
-use termion::color;
+use crossterm::style::{ResetColor, SetForegroundColor};
 use unicode_segmentation::UnicodeSegmentation;
 
 #[derive(Default)]
 pub struct Row {
     string: String,
     highlighting: Vec<highlighting::Type>,
+    pub(crate) is_highlighted: bool,
     len: usize,
 }
 
@@ -17,6 +19,7 @@ impl From<&str> for Row {
         let mut row = Self {
             string: String::from(slice),
             highlighting: Vec::new(),
+            is_highlighted: false,
             len: 0,
         };
 
@@ -26,37 +29,68 @@ impl From<&str> for Row {
 }
 
 impl Row {
-    pub fn render(&self, start: usize, end: usize) -> String {
-        let end = cmp::min(end, self.string.len());
-        let start = cmp::min(start, end);
-        // self.string.get(start..end).unwrap_or_default().to_string()
+    // `start`/`end` are rendered columns (post tab-expansion), matching the
+    // space `cx_to_rx` computes cursor columns in.
+    pub fn render(&self, start: usize, end: usize, tab_stop: usize) -> String {
         let mut result = String::new();
+        let mut current_highlighting = highlighting::Type::None;
+        let mut rx = 0;
         // loop over graphemes instead of ascii characters (Unicode support)
-        for grapheme in self.string[..]
-            .graphemes(true)
-            .skip(start)
-            .take(end - start)
-        {
-            if let Some(c) = grapheme.chars().next() {
-                if c == '\t' {
-                    result.push_str("  ");
-                } else if c.is_ascii_digit() {
+        for (index, grapheme) in self.string[..].graphemes(true).enumerate() {
+            if rx >= end {
+                break;
+            }
+            let c = match grapheme.chars().next() {
+                Some(c) => c,
+                None => continue,
+            };
+            let width = if c == '\t' {
+                tab_stop - (rx % tab_stop)
+            } else {
+                1
+            };
+
+            if rx + width > start {
+                let highlighting_type = *self
+                    .highlighting
+                    .get(index)
+                    .unwrap_or(&highlighting::Type::None);
+                // only emit an escape when the highlight actually changes, so we
+                // don't write a redundant SetForegroundColor per character
+                if highlighting_type != current_highlighting {
+                    current_highlighting = highlighting_type;
                     result.push_str(
-                        &format!(
-                            "{}{}{}",
-                            color::Fg(color::Rgb(220, 163, 163)),
-                            c,
-                            color::Fg(color::Reset),
-                        )[..],
+                        &format!("{}", SetForegroundColor(highlighting_type.to_color()))[..],
                     );
-                } else {
+                }
+                if c == '\t' {
+                    for _ in cmp::max(rx, start)..cmp::min(rx + width, end) {
+                        result.push(' ');
+                    }
+                } else if rx >= start {
                     result.push(c);
                 }
             }
+            rx += width;
         }
+        result.push_str(&format!("{}", ResetColor)[..]);
         result
     }
 
+    // translate a cursor column (grapheme index) into a rendered column,
+    // expanding each tab up to the next `tab_stop` boundary
+    pub fn cx_to_rx(&self, cx: usize, tab_stop: usize) -> usize {
+        let mut rx = 0;
+        for grapheme in self.string[..].graphemes(true).take(cx) {
+            if grapheme == "\t" {
+                rx += tab_stop - (rx % tab_stop);
+            } else {
+                rx += 1;
+            }
+        }
+        rx
+    }
+
     pub fn len(&self) -> usize {
         self.len
     }
@@ -114,31 +148,196 @@ impl Row {
         self.string.as_bytes()
     }
 
+    pub fn as_str(&self) -> &str {
+        &self.string
+    }
+
+    // locate `query`'s first occurrence at or after the `after`'th
+    // grapheme, returning a grapheme index (not a byte offset)
     pub fn find(&self, query: &str, after: usize) -> Option<usize> {
-        let substring: String = self.string[..].graphemes(true).skip(after).collect();
-        let matching_byte_index = self.string.find(query);
-        if let Some(matching_byte_index) = matching_byte_index {
-            for (grapheme_index, (byte_index, _)) in 
-                substring[..].grapheme_indices(true).enumerate()
+        if after > self.len {
+            return None;
+        }
+        let start_byte = self.byte_index_of(after);
+        self.string[start_byte..].find(query).map(|byte_index| {
+            after + self.string[start_byte..start_byte + byte_index]
+                .graphemes(true)
+                .count()
+        })
+    }
+
+    // locate `query`'s last occurrence strictly before the `before`'th
+    // grapheme; used to step backwards through matches during search
+    pub fn rfind(&self, query: &str, before: usize) -> Option<usize> {
+        let end_byte = self.byte_index_of(cmp::min(before, self.len));
+        self.string[..end_byte]
+            .rfind(query)
+            .map(|byte_index| self.string[..byte_index].graphemes(true).count())
+    }
+
+    // byte offset of the `grapheme_index`'th grapheme, or the row's total
+    // byte length if it runs past the end
+    fn byte_index_of(&self, grapheme_index: usize) -> usize {
+        self.string[..]
+            .grapheme_indices(true)
+            .nth(grapheme_index)
+            .map_or(self.string.len(), |(byte_index, _)| byte_index)
+    }
+
+    /// Recompute this row's highlighting from scratch. When `word` is given,
+    /// every occurrence of it is additionally marked `Type::Match` (used to
+    /// light up search hits). Skipped when the row is already highlighted
+    /// and no word highlighting is requested.
+    pub fn highlight(&mut self, opts: &HighlightingOptions, word: Option<&str>) {
+        if self.is_highlighted && word.is_none() {
+            return;
+        }
+
+        let graphemes: Vec<&str> = self.string[..].graphemes(true).collect();
+        let mut highlighting = Vec::with_capacity(graphemes.len());
+
+        let mut index = 0;
+        while index < graphemes.len() {
+            if opts.comments()
+                && !opts.comment_start().is_empty()
+                && Self::matches_at(&graphemes, index, opts.comment_start())
             {
-                if matching_byte_index == byte_index {
-                    #[allow(clippy::integer_arithmetic)]
-                    return Some(grapheme_index);
+                while index < graphemes.len() {
+                    highlighting.push(highlighting::Type::Comment);
+                    index += 1;
+                }
+                break;
+            }
+
+            let grapheme = graphemes[index];
+            if opts.strings() && (grapheme == "\"" || (opts.characters() && grapheme == "'")) {
+                let quote = grapheme;
+                highlighting.push(highlighting::Type::String);
+                index += 1;
+                while index < graphemes.len() {
+                    let c = graphemes[index];
+                    if c == "\\" && index + 1 < graphemes.len() {
+                        highlighting.push(highlighting::Type::String);
+                        highlighting.push(highlighting::Type::String);
+                        index += 2;
+                        continue;
+                    }
+                    highlighting.push(highlighting::Type::String);
+                    index += 1;
+                    if c == quote {
+                        break;
+                    }
+                }
+                continue;
+            }
+
+            if opts.numbers() {
+                let is_digit = grapheme
+                    .chars()
+                    .next()
+                    .map_or(false, |c| c.is_ascii_digit());
+                let is_leading_dot =
+                    grapheme == "." && highlighting.last() == Some(&highlighting::Type::Number);
+                if is_digit || is_leading_dot {
+                    highlighting.push(highlighting::Type::Number);
+                    index += 1;
+                    continue;
+                }
+            }
+
+            if Self::is_word_start(&graphemes, index) {
+                if let Some(len) =
+                    Self::longest_keyword_match(&graphemes, index, opts.primary_keywords())
+                {
+                    for _ in 0..len {
+                        highlighting.push(highlighting::Type::PrimaryKeywords);
+                    }
+                    index += len;
+                    continue;
+                }
+                if let Some(len) =
+                    Self::longest_keyword_match(&graphemes, index, opts.secondary_keywords())
+                {
+                    for _ in 0..len {
+                        highlighting.push(highlighting::Type::SecondaryKeywords);
+                    }
+                    index += len;
+                    continue;
                 }
             }
+
+            highlighting.push(highlighting::Type::None);
+            index += 1;
+        }
+
+        self.highlighting = highlighting;
+        self.is_highlighted = true;
+
+        if let Some(word) = word {
+            self.highlight_word(word);
         }
-        None
     }
 
-    pub fn highlight(&mut self) {
-        let mut highlighting = Vec::new();
-        for c in self.string.chars() {
-            if c.is_ascii_digit() {
-                highlighting.push(highlighting::Type::Number);
-            } else {
-                highlighting.push(highlighting::Type::None);
+    /// Mark `self.highlighting[from..to]` as `Type::Match`, e.g. for the
+    /// substring a search just matched.
+    pub fn highlight_match(&mut self, from: usize, to: usize) {
+        for highlight in self.highlighting[..].iter_mut().take(to).skip(from) {
+            *highlight = highlighting::Type::Match;
+        }
+    }
+
+    fn highlight_word(&mut self, word: &str) {
+        if word.is_empty() {
+            return;
+        }
+        let len = word[..].graphemes(true).count();
+        let mut start = 0;
+        while let Some(index) = self.find(word, start) {
+            self.highlight_match(index, index.saturating_add(len));
+            match index.checked_add(len) {
+                Some(next) => start = next,
+                None => break,
             }
         }
-        self.highlighting = highlighting;
     }
-}
\ No newline at end of file
+
+    fn matches_at(graphemes: &[&str], index: usize, needle: &str) -> bool {
+        let needle_graphemes: Vec<&str> = needle.graphemes(true).collect();
+        let needle_len = needle_graphemes.len();
+        index + needle_len <= graphemes.len()
+            && graphemes[index..index + needle_len] == needle_graphemes[..]
+    }
+
+    fn is_separator(grapheme: &str) -> bool {
+        grapheme
+            .chars()
+            .next()
+            .map_or(true, |c| !c.is_alphanumeric() && c != '_')
+    }
+
+    fn is_word_start(graphemes: &[&str], index: usize) -> bool {
+        index == 0 || Self::is_separator(graphemes[index - 1])
+    }
+
+    // match the longest keyword starting at `index` that also ends on a word
+    // boundary, so e.g. "structure" doesn't highlight the "struct" prefix
+    fn longest_keyword_match(
+        graphemes: &[&str],
+        index: usize,
+        keywords: &[String],
+    ) -> Option<usize> {
+        let mut best: Option<usize> = None;
+        for keyword in keywords {
+            if !Self::matches_at(graphemes, index, keyword) {
+                continue;
+            }
+            let len = keyword[..].graphemes(true).count();
+            let at_boundary =
+                index + len == graphemes.len() || Self::is_separator(graphemes[index + len]);
+            if at_boundary && best.map_or(true, |best_len| len > best_len) {
+                best = Some(len);
+            }
+        }
+        best
+    }
+}
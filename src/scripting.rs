@@ -0,0 +1,138 @@
+use std::cell::RefCell;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+use rhai::{Engine, Scope, AST};
+
+use crate::editor::Position;
+
+const SCRIPT_FILE_NAME: &str = "scripts.rhai";
+
+/// State a scripted command reads and mutates through `EditorApi`. Applied
+/// back onto the real `Editor`/`Document` once the script returns.
+pub struct ApiState {
+    pub current_line: String,
+    pub cursor: Position,
+    pub insert_queue: String,
+    pub status_message: Option<String>,
+}
+
+/// Handle passed into Rhai so scripts can poke at the running editor.
+/// Shared via `Rc<RefCell<_>>` because Rhai's registered functions take
+/// the type by value/clone, not by Rust reference.
+#[derive(Clone)]
+pub struct EditorApi {
+    state: Rc<RefCell<ApiState>>,
+}
+
+impl EditorApi {
+    pub fn new(current_line: String, cursor: Position) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(ApiState {
+                current_line,
+                cursor,
+                insert_queue: String::new(),
+                status_message: None,
+            })),
+        }
+    }
+
+    pub fn into_state(self) -> ApiState {
+        Rc::try_unwrap(self.state)
+            .unwrap_or_else(|shared| RefCell::new(shared.borrow().clone_state()))
+            .into_inner()
+    }
+
+    fn get_line(&mut self) -> String {
+        self.state.borrow().current_line.clone()
+    }
+
+    fn set_line(&mut self, text: String) {
+        self.state.borrow_mut().current_line = text;
+    }
+
+    fn insert_text(&mut self, text: String) {
+        self.state.borrow_mut().insert_queue.push_str(&text);
+    }
+
+    fn move_cursor(&mut self, dx: i64, dy: i64) {
+        let mut state = self.state.borrow_mut();
+        state.cursor.x = Self::apply_delta(state.cursor.x, dx);
+        state.cursor.y = Self::apply_delta(state.cursor.y, dy);
+    }
+
+    fn apply_delta(value: usize, delta: i64) -> usize {
+        if delta < 0 {
+            value.saturating_sub(delta.unsigned_abs() as usize)
+        } else {
+            value.saturating_add(delta as usize)
+        }
+    }
+
+    fn set_status_message(&mut self, message: String) {
+        self.state.borrow_mut().status_message = Some(message);
+    }
+}
+
+impl ApiState {
+    fn clone_state(&self) -> Self {
+        Self {
+            current_line: self.current_line.clone(),
+            cursor: Position {
+                x: self.cursor.x,
+                y: self.cursor.y,
+            },
+            insert_queue: self.insert_queue.clone(),
+            status_message: self.status_message.clone(),
+        }
+    }
+}
+
+/// Embeds a Rhai engine and the user's `scripts.rhai`, exposing the small
+/// editing API scripted commands are invoked against.
+pub struct Scripting {
+    engine: Engine,
+    ast: Option<AST>,
+}
+
+impl Scripting {
+    /// Load `scripts.rhai` from the user's config directory, registering
+    /// the scripted API surface regardless of whether a script file is
+    /// present.
+    pub fn load() -> Self {
+        let mut engine = Engine::new();
+        engine
+            .register_type::<EditorApi>()
+            .register_fn("get_line", EditorApi::get_line)
+            .register_fn("set_line", EditorApi::set_line)
+            .register_fn("insert_text", EditorApi::insert_text)
+            .register_fn("move_cursor", EditorApi::move_cursor)
+            .register_fn("set_status_message", EditorApi::set_status_message);
+
+        let ast = Self::script_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|source| engine.compile(source).ok());
+
+        Self { engine, ast }
+    }
+
+    /// Run the scripted function `name`, returning the mutated API state
+    /// on success or an error message (meant for the status bar) on
+    /// failure.
+    pub fn run(&self, name: &str, api: EditorApi) -> Result<ApiState, String> {
+        let ast = self
+            .ast
+            .as_ref()
+            .ok_or_else(|| "No scripts loaded".to_string())?;
+        let mut scope = Scope::new();
+        self.engine
+            .call_fn::<()>(&mut scope, ast, name, (api.clone(),))
+            .map_err(|error| error.to_string())?;
+        Ok(api.into_state())
+    }
+
+    fn script_path() -> Option<std::path::PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("loop").join(Path::new(SCRIPT_FILE_NAME)))
+    }
+}
@@ -0,0 +1,231 @@
+use crate::Position;
+
+use std::io::{self, stdout, Write};
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::style::{Color, ResetColor, SetBackgroundColor, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+
+/// Backend-neutral key, so `Editor` never has to match on a specific
+/// terminal crate's event type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Ctrl(char),
+    Alt(char),
+    Backspace,
+    Delete,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Esc,
+}
+
+pub struct Size {
+    pub width: u16,
+    pub height: u16,
+}
+
+/// Everything `Terminal` needs from the underlying terminal library.
+/// Input and terminal control reach `editor.rs` only as `Key`/`Size`/
+/// `Terminal`. Colors are not abstracted: `editor.rs`, `row.rs` and
+/// `highlighting.rs` use crossterm's `Color` and style commands directly.
+trait Backend {
+    fn size(&self) -> io::Result<(u16, u16)>;
+    fn clear_screen(&self) -> io::Result<()>;
+    fn clear_current_line(&self) -> io::Result<()>;
+    fn cursor_position(&self, position: &Position) -> io::Result<()>;
+    fn cursor_hide(&self) -> io::Result<()>;
+    fn cursor_show(&self) -> io::Result<()>;
+    fn flush(&self) -> io::Result<()>;
+    fn read_key(&self) -> io::Result<Key>;
+    fn set_bg_color(&self, color: Color) -> io::Result<()>;
+    fn reset_bg_color(&self) -> io::Result<()>;
+    fn set_fg_color(&self, color: Color) -> io::Result<()>;
+    fn reset_fg_color(&self) -> io::Result<()>;
+}
+
+/// The default (and, today, only) backend: crossterm, so the same
+/// binary runs unmodified on Linux, macOS and Windows.
+struct CrosstermBackend;
+
+impl Backend for CrosstermBackend {
+    fn size(&self) -> io::Result<(u16, u16)> {
+        terminal::size()
+    }
+
+    fn clear_screen(&self) -> io::Result<()> {
+        execute!(stdout(), Clear(ClearType::All))
+    }
+
+    fn clear_current_line(&self) -> io::Result<()> {
+        execute!(stdout(), Clear(ClearType::CurrentLine))
+    }
+
+    fn cursor_position(&self, position: &Position) -> io::Result<()> {
+        let Position { x, y } = position;
+        queue!(stdout(), MoveTo(*x as u16, *y as u16))
+    }
+
+    fn cursor_hide(&self) -> io::Result<()> {
+        queue!(stdout(), Hide)
+    }
+
+    fn cursor_show(&self) -> io::Result<()> {
+        queue!(stdout(), Show)
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        stdout().flush()
+    }
+
+    fn read_key(&self) -> io::Result<Key> {
+        loop {
+            if let Event::Key(KeyEvent {
+                code, modifiers, ..
+            }) = event::read()?
+            {
+                if let Some(key) = translate_key(code, modifiers) {
+                    return Ok(key);
+                }
+            }
+        }
+    }
+
+    fn set_bg_color(&self, color: Color) -> io::Result<()> {
+        queue!(stdout(), SetBackgroundColor(color))
+    }
+
+    fn reset_bg_color(&self) -> io::Result<()> {
+        queue!(stdout(), ResetColor)
+    }
+
+    fn set_fg_color(&self, color: Color) -> io::Result<()> {
+        queue!(stdout(), SetForegroundColor(color))
+    }
+
+    fn reset_fg_color(&self) -> io::Result<()> {
+        queue!(stdout(), ResetColor)
+    }
+}
+
+// crossterm reports modifiers separately from the key itself; fold them
+// back into the termion-style `Key::Ctrl`/`Key::Alt` variants the rest of
+// the editor already matches on, and drop keys that have no `Key` variant.
+// The event kind (press, repeat, release) is not examined.
+fn translate_key(code: KeyCode, modifiers: KeyModifiers) -> Option<Key> {
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        if let KeyCode::Char(c) = code {
+            return Some(Key::Ctrl(c));
+        }
+    }
+    if modifiers.contains(KeyModifiers::ALT) {
+        if let KeyCode::Char(c) = code {
+            return Some(Key::Alt(c));
+        }
+    }
+    match code {
+        KeyCode::Char(c) => Some(Key::Char(c)),
+        KeyCode::Enter => Some(Key::Char('\n')),
+        KeyCode::Tab => Some(Key::Char('\t')),
+        KeyCode::Backspace => Some(Key::Backspace),
+        KeyCode::Delete => Some(Key::Delete),
+        KeyCode::Up => Some(Key::Up),
+        KeyCode::Down => Some(Key::Down),
+        KeyCode::Left => Some(Key::Left),
+        KeyCode::Right => Some(Key::Right),
+        KeyCode::Home => Some(Key::Home),
+        KeyCode::End => Some(Key::End),
+        KeyCode::PageUp => Some(Key::PageUp),
+        KeyCode::PageDown => Some(Key::PageDown),
+        KeyCode::Esc => Some(Key::Esc),
+        _ => None,
+    }
+}
+
+/// Restores the terminal to its original mode on drop, whatever the exit
+/// path — this is what used to be `termion`'s `RawTerminal` guard.
+struct RawModeGuard;
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        let _ = execute!(stdout(), LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+pub struct Terminal {
+    size: Size,
+    _raw_mode: RawModeGuard,
+}
+
+impl Terminal {
+    pub fn default() -> Result<Self, std::io::Error> {
+        terminal::enable_raw_mode()?;
+        execute!(stdout(), EnterAlternateScreen)?;
+        let (width, height) = CrosstermBackend.size()?;
+        Ok(Self {
+            size: Size {
+                width,
+                // leave room for the status bar and message bar
+                height: height.saturating_sub(2),
+            },
+            _raw_mode: RawModeGuard,
+        })
+    }
+
+    pub fn size(&self) -> &Size {
+        &self.size
+    }
+
+    pub fn clear_screen() {
+        CrosstermBackend.clear_screen().unwrap_or(());
+    }
+
+    pub fn clear_current_line() {
+        CrosstermBackend.clear_current_line().unwrap_or(());
+    }
+
+    pub fn cursor_position(position: &Position) {
+        CrosstermBackend.cursor_position(position).unwrap_or(());
+    }
+
+    pub fn cursor_hide() {
+        CrosstermBackend.cursor_hide().unwrap_or(());
+    }
+
+    pub fn cursor_show() {
+        CrosstermBackend.cursor_show().unwrap_or(());
+    }
+
+    pub fn flush() -> Result<(), std::io::Error> {
+        CrosstermBackend.flush()
+    }
+
+    pub fn read_key() -> Result<Key, std::io::Error> {
+        CrosstermBackend.read_key()
+    }
+
+    pub fn set_bg_color(color: Color) {
+        CrosstermBackend.set_bg_color(color).unwrap_or(());
+    }
+
+    pub fn reset_bg_color() {
+        CrosstermBackend.reset_bg_color().unwrap_or(());
+    }
+
+    pub fn set_fg_color(color: Color) {
+        CrosstermBackend.set_fg_color(color).unwrap_or(());
+    }
+
+    pub fn reset_fg_color() {
+        CrosstermBackend.reset_fg_color().unwrap_or(());
+    }
+}